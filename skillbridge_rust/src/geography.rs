@@ -0,0 +1,124 @@
+//! Geographic matching seeded from a small country -> subregion -> continent
+//! hierarchy (the structure CLDR/ICU region data uses, keyed by ISO-3166
+//! country codes) plus an approximate UTC offset per country, so location
+//! matching can return a graded affinity instead of a single same/different
+//! boolean, and mentoring-hours overlap can account for timezone gaps.
+
+struct CountryInfo {
+    /// ISO-3166-1 alpha-2 code.
+    iso_code: &'static str,
+    subregion: &'static str,
+    continent: &'static str,
+    /// Approximate standard-time UTC offset, in hours.
+    utc_offset_hours: f64,
+    /// Free-text aliases this country is recognized under in a location string.
+    aliases: &'static [&'static str],
+}
+
+const COUNTRIES: &[CountryInfo] = &[
+    CountryInfo { iso_code: "NG", subregion: "Western Africa", continent: "Africa", utc_offset_hours: 1.0, aliases: &["nigeria"] },
+    CountryInfo { iso_code: "GH", subregion: "Western Africa", continent: "Africa", utc_offset_hours: 0.0, aliases: &["ghana"] },
+    CountryInfo { iso_code: "KE", subregion: "Eastern Africa", continent: "Africa", utc_offset_hours: 3.0, aliases: &["kenya"] },
+    CountryInfo { iso_code: "UG", subregion: "Eastern Africa", continent: "Africa", utc_offset_hours: 3.0, aliases: &["uganda"] },
+    CountryInfo { iso_code: "ZA", subregion: "Southern Africa", continent: "Africa", utc_offset_hours: 2.0, aliases: &["south africa"] },
+    CountryInfo { iso_code: "GB", subregion: "Western Europe", continent: "Europe", utc_offset_hours: 0.0, aliases: &["uk", "united kingdom"] },
+    CountryInfo { iso_code: "DE", subregion: "Western Europe", continent: "Europe", utc_offset_hours: 1.0, aliases: &["germany"] },
+    CountryInfo { iso_code: "FR", subregion: "Western Europe", continent: "Europe", utc_offset_hours: 1.0, aliases: &["france"] },
+    CountryInfo { iso_code: "ES", subregion: "Southern Europe", continent: "Europe", utc_offset_hours: 1.0, aliases: &["spain"] },
+    CountryInfo { iso_code: "IT", subregion: "Southern Europe", continent: "Europe", utc_offset_hours: 1.0, aliases: &["italy"] },
+    CountryInfo { iso_code: "IN", subregion: "Southern Asia", continent: "Asia", utc_offset_hours: 5.5, aliases: &["india"] },
+    CountryInfo { iso_code: "CN", subregion: "Eastern Asia", continent: "Asia", utc_offset_hours: 8.0, aliases: &["china"] },
+    CountryInfo { iso_code: "JP", subregion: "Eastern Asia", continent: "Asia", utc_offset_hours: 9.0, aliases: &["japan"] },
+    CountryInfo { iso_code: "SG", subregion: "South-Eastern Asia", continent: "Asia", utc_offset_hours: 8.0, aliases: &["singapore"] },
+    CountryInfo { iso_code: "US", subregion: "Northern America", continent: "Americas", utc_offset_hours: -5.0, aliases: &["usa", "united states"] },
+    CountryInfo { iso_code: "CA", subregion: "Northern America", continent: "Americas", utc_offset_hours: -5.0, aliases: &["canada"] },
+    CountryInfo { iso_code: "BR", subregion: "South America", continent: "Americas", utc_offset_hours: -3.0, aliases: &["brazil"] },
+    CountryInfo { iso_code: "MX", subregion: "Central America", continent: "Americas", utc_offset_hours: -6.0, aliases: &["mexico"] },
+];
+
+/// Matches aliases against whole words in `location`, not substrings, so e.g.
+/// "Ukraine" doesn't collide with GB's "uk" alias the way a raw `contains`
+/// check would.
+fn lookup(location: &str) -> Option<&'static CountryInfo> {
+    let location = location.to_lowercase();
+    let words: Vec<&str> = location
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    COUNTRIES.iter().find(|country| {
+        country.aliases.iter().any(|&alias| {
+            let alias_words: Vec<&str> = alias.split_whitespace().collect();
+            words.windows(alias_words.len()).any(|window| window == alias_words.as_slice())
+        })
+    })
+}
+
+/// Graded 0.0-1.0 geographic affinity: 1.0 for the same country, ~0.7 for the
+/// same subregion, ~0.4 for the same continent, 0.0 otherwise. Falls back to
+/// a case-insensitive string comparison when either location isn't recognized.
+pub fn location_affinity(loc1: &str, loc2: &str) -> f64 {
+    match (lookup(loc1), lookup(loc2)) {
+        (Some(a), Some(b)) => {
+            if a.iso_code == b.iso_code {
+                1.0
+            } else if a.subregion == b.subregion {
+                0.7
+            } else if a.continent == b.continent {
+                0.4
+            } else {
+                0.0
+            }
+        }
+        _ => {
+            if loc1.to_lowercase() == loc2.to_lowercase() {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+/// Fraction of an assumed 8-hour working day that two locations' standard
+/// working hours overlap, based on their approximate UTC offsets. Unknown
+/// locations are assumed to overlap moderately rather than penalized, since
+/// we simply lack timezone data for them.
+pub fn timezone_overlap_fraction(loc1: &str, loc2: &str) -> f64 {
+    const WORKING_DAY_HOURS: f64 = 8.0;
+
+    match (lookup(loc1), lookup(loc2)) {
+        (Some(a), Some(b)) => {
+            let offset_diff = (a.utc_offset_hours - b.utc_offset_hours).abs();
+            ((WORKING_DAY_HOURS - offset_diff).max(0.0) / WORKING_DAY_HOURS).min(1.0)
+        }
+        _ => 0.5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_affinity_grades_by_hierarchy() {
+        assert_eq!(location_affinity("Nigeria", "Nigeria"), 1.0);
+        assert_eq!(location_affinity("Kenya", "Uganda"), 0.7); // same subregion
+        assert_eq!(location_affinity("Kenya", "South Africa"), 0.4); // same continent
+        assert_eq!(location_affinity("Nigeria", "Germany"), 0.0);
+    }
+
+    #[test]
+    fn test_alias_matching_is_whole_word_not_substring() {
+        // "ukraine" contains the substring "uk", GB's alias, but isn't GB.
+        assert_eq!(location_affinity("Ukraine", "Germany"), 0.0);
+    }
+
+    #[test]
+    fn test_timezone_overlap_shrinks_with_offset_gap() {
+        let close = timezone_overlap_fraction("Nigeria", "Germany"); // +1 vs +1, no gap
+        let far = timezone_overlap_fraction("Nigeria", "Japan"); // +1 vs +9, 8h gap
+        assert_eq!(close, 1.0);
+        assert_eq!(far, 0.0);
+    }
+}
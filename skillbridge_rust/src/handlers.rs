@@ -1,11 +1,8 @@
 use actix_web::{web, HttpResponse, Result};
-use serde_json::json;
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
-use crate::models::{MatchingRequest, MatchingResponse, HealthCheck, MatchingStats};
-use crate::matching::MentorMatcher;
-
-static mut REQUEST_COUNT: u64 = 0;
-static mut TOTAL_PROCESSING_TIME: u64 = 0;
+use std::time::Instant;
+use crate::models::{MatchingRequest, MatchingResponse, HealthCheck};
+use crate::matching::{MentorMatcher, DEFAULT_SKILL_MATCH_THRESHOLD, DEFAULT_RANKING_RULES};
+use crate::state::{self, AppState};
 
 pub async fn health_check() -> Result<HttpResponse> {
     let timestamp = chrono::Utc::now();
@@ -19,70 +16,53 @@ pub async fn health_check() -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(health))
 }
 
-pub async fn match_mentors(req: web::Json<MatchingRequest>) -> Result<HttpResponse> {
+pub async fn match_mentors(app_state: web::Data<AppState>, req: web::Json<MatchingRequest>) -> Result<HttpResponse> {
     let start_time = Instant::now();
     let matcher = MentorMatcher::new();
 
-    // Update request count (in production, use atomic operations)
-    unsafe {
-        REQUEST_COUNT += 1;
-    }
-
     let limit = req.limit.unwrap_or(5).min(20); // Cap at 20 for performance
+    let skill_match_threshold = req.skill_match_threshold.unwrap_or(DEFAULT_SKILL_MATCH_THRESHOLD);
+    let ranking_rules = req.ranking_rules.clone().unwrap_or_else(|| DEFAULT_RANKING_RULES.to_vec());
+    let normalize_skills = !req.disable_skill_normalization.unwrap_or(false);
+
+    let cache_key = state::compute_cache_key(&req, &ranking_rules, skill_match_threshold, normalize_skills, limit);
+
+    if let Some(cached) = app_state.get_cached(cache_key) {
+        app_state.record_cache_hit();
+        app_state.record_request(start_time.elapsed().as_millis() as u64);
+        log::info!("Served matching response from cache");
+        return Ok(HttpResponse::Ok().json(cached));
+    }
 
     log::info!("Processing matching request for {} mentors, limit: {}", req.mentors.len(), limit);
 
     // Perform matching
-    let matches = matcher.find_matches(&req.learner, &req.mentors, limit);
+    let matches = matcher.find_matches(&req.learner, &req.mentors, limit, skill_match_threshold, &ranking_rules, normalize_skills);
     let matches_count = matches.len();
 
     let processing_time = start_time.elapsed().as_millis() as u64;
 
-    // Update total processing time
-    unsafe {
-        TOTAL_PROCESSING_TIME += processing_time;
-    }
-
     let response = MatchingResponse {
         matches,
         processing_time_ms: processing_time,
         algorithm_version: matcher.algorithm_version.clone(),
     };
 
+    app_state.record_request(processing_time);
+    app_state.cache_response(cache_key, response.clone());
+
     log::info!("Matching completed in {}ms, found {} matches", processing_time, matches_count);
 
     Ok(HttpResponse::Ok().json(response))
 }
 
-pub async fn get_matching_stats() -> Result<HttpResponse> {
-    let uptime = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-
-    unsafe {
-        let request_count = REQUEST_COUNT;
-        let avg_processing_time = if request_count > 0 {
-            TOTAL_PROCESSING_TIME as f64 / request_count as f64
-        } else {
-            0.0
-        };
-
-        let stats = MatchingStats {
-            total_requests: request_count,
-            average_processing_time_ms: avg_processing_time,
-            cache_hit_rate: 0.0, // Not implemented yet
-            uptime_seconds: uptime,
-        };
-
-        Ok(HttpResponse::Ok().json(stats))
-    }
+pub async fn get_matching_stats(app_state: web::Data<AppState>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(app_state.stats()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use actix_web::test;
     use crate::models::{User, Mentor};
     use uuid::Uuid;
 
@@ -92,8 +72,7 @@ mod tests {
         assert_eq!(resp.status(), 200);
     }
 
-    #[actix_web::test]
-    async fn test_matching_request() {
+    fn sample_request() -> MatchingRequest {
         let learner = User {
             id: Uuid::new_v4(),
             skills: vec!["python".to_string()],
@@ -115,17 +94,65 @@ mod tests {
             teaching_style: "structured".to_string(),
         };
 
-        let request = MatchingRequest {
+        MatchingRequest {
             learner,
             mentors: vec![mentor],
             limit: Some(5),
+            skill_match_threshold: None,
+            ranking_rules: None,
+            disable_skill_normalization: None,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_matching_request() {
+        let app_state = web::Data::new(AppState::new());
+        let resp = match_mentors(app_state, web::Json(sample_request())).await.unwrap();
+        assert_eq!(resp.status(), 200);
+    }
+
+    #[actix_web::test]
+    async fn test_cache_hit_returns_identical_response_and_reports_hit_rate() {
+        let app_state = web::Data::new(AppState::new());
+        let learner = User {
+            id: Uuid::new_v4(),
+            skills: vec!["python".to_string()],
+            learning_goals: vec![],
+            location: "Nigeria".to_string(),
+            availability: 10,
+            experience_level: "beginner".to_string(),
+            preferred_languages: vec![],
+        };
+        let mentor = Mentor {
+            id: Uuid::new_v4(),
+            expertise: vec!["python".to_string()],
+            location: "Nigeria".to_string(),
+            availability: 15,
+            experience_years: 3,
+            rating: 4.5,
+            hourly_rate: 50,
+            teaching_style: "structured".to_string(),
+        };
+
+        let request_builder = |learner: &User, mentor: &Mentor| MatchingRequest {
+            learner: learner.clone(),
+            mentors: vec![mentor.clone()],
+            limit: Some(5),
+            skill_match_threshold: None,
+            ranking_rules: None,
+            disable_skill_normalization: None,
         };
 
-        let req = test::TestRequest::post()
-            .set_json(&request)
-            .to_http_request();
+        let cold = match_mentors(app_state.clone(), web::Json(request_builder(&learner, &mentor))).await.unwrap();
+        let cold_body = actix_web::body::to_bytes(cold.into_body()).await.unwrap();
 
-        let resp = match_mentors(web::Json(request)).await.unwrap();
-        assert_eq!(resp.status(), 200);
+        let cached = match_mentors(app_state.clone(), web::Json(request_builder(&learner, &mentor))).await.unwrap();
+        let cached_body = actix_web::body::to_bytes(cached.into_body()).await.unwrap();
+
+        assert_eq!(cold_body, cached_body);
+
+        let stats = app_state.stats();
+        assert_eq!(stats.total_requests, 2);
+        assert_eq!(stats.cache_hit_rate, 0.5);
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,15 @@
 mod matching;
 mod models;
 mod handlers;
+mod normalization;
+mod geography;
+mod state;
 
 use actix_web::{web, App, HttpServer, middleware::Logger};
 use actix_cors::Cors;
 use env_logger::Env;
 use std::env;
+use state::AppState;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -18,7 +22,9 @@ async fn main() -> std::io::Result<()> {
 
     log::info!("Starting SkillBridge Matching Service on {}", address);
 
-    HttpServer::new(|| {
+    let app_state = web::Data::new(AppState::new());
+
+    HttpServer::new(move || {
         let cors = Cors::default()
             .allow_any_origin()
             .allow_any_method()
@@ -26,6 +32,7 @@ async fn main() -> std::io::Result<()> {
             .max_age(3600);
 
         App::new()
+            .app_data(app_state.clone())
             .wrap(cors)
             .wrap(Logger::default())
             .route("/health", web::get().to(handlers::health_check))
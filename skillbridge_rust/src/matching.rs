@@ -1,12 +1,23 @@
-use crate::models::{User, Mentor, MatchResult, CompatibilityFactors};
-use petgraph::graph::{Graph, NodeIndex};
-use petgraph::algo::matching;
+use crate::models::{User, Mentor, MatchResult, CompatibilityFactors, MatchReason, RankingRule};
+use crate::normalization;
+use crate::geography;
 use rayon::prelude::*;
-use std::collections::HashMap;
-use std::time::{Instant, Duration};
+use std::time::Instant;
+
+/// Default minimum fuzzy similarity for a skill to count as a match,
+/// used when a request doesn't override `skill_match_threshold`.
+pub const DEFAULT_SKILL_MATCH_THRESHOLD: f64 = 0.8;
+
+/// Default ranking rules, used when a request doesn't override
+/// `ranking_rules`. Preserves the original single-score sort.
+pub const DEFAULT_RANKING_RULES: &[RankingRule] = &[RankingRule::Weighted];
+
+/// Two rule values within this distance of each other are considered tied,
+/// so the comparison falls through to the next ranking rule.
+const RANKING_EPSILON: f64 = 1e-6;
 
 pub struct MentorMatcher {
-    algorithm_version: String,
+    pub algorithm_version: String,
 }
 
 impl MentorMatcher {
@@ -16,7 +27,7 @@ impl MentorMatcher {
         }
     }
 
-    pub fn find_matches(&self, learner: &User, mentors: &[Mentor], limit: usize) -> Vec<MatchResult> {
+    pub fn find_matches(&self, learner: &User, mentors: &[Mentor], limit: usize, skill_match_threshold: f64, ranking_rules: &[RankingRule], normalize_skills: bool) -> Vec<MatchResult> {
         let start_time = Instant::now();
 
         // Calculate compatibility scores for all mentor-learner pairs
@@ -24,14 +35,30 @@ impl MentorMatcher {
             .par_iter()
             .enumerate()
             .map(|(index, mentor)| {
-                let score = self.calculate_compatibility_score(learner, mentor);
-                let factors = self.calculate_compatibility_factors(learner, mentor);
+                let score = self.calculate_compatibility_score(learner, mentor, skill_match_threshold, normalize_skills);
+                let factors = self.calculate_compatibility_factors(learner, mentor, skill_match_threshold, normalize_skills);
                 (index, score, factors)
             })
             .collect();
 
-        // Sort by compatibility score (descending)
-        compatibility_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        // Sort lexicographically: compare on the first ranking rule, only
+        // falling through to the next rule when candidates are tied on it.
+        // A final tiebreak on mentor id guarantees a total order, so mentors
+        // tied on every rule (including `Weighted`) always land in the same
+        // output order regardless of input order - `sort_by` is stable, so
+        // without this the input order would otherwise leak through.
+        compatibility_scores.sort_by(|a, b| {
+            let mentor_a = &mentors[a.0];
+            let mentor_b = &mentors[b.0];
+            for &rule in ranking_rules {
+                let value_a = self.ranking_rule_value(rule, mentor_a, a.1, &a.2);
+                let value_b = self.ranking_rule_value(rule, mentor_b, b.1, &b.2);
+                if (value_a - value_b).abs() > RANKING_EPSILON {
+                    return value_b.partial_cmp(&value_a).unwrap_or(std::cmp::Ordering::Equal);
+                }
+            }
+            mentor_a.id.cmp(&mentor_b.id)
+        });
 
         // Take top matches
         let top_matches: Vec<MatchResult> = compatibility_scores
@@ -40,10 +67,12 @@ impl MentorMatcher {
             .enumerate()
             .map(|(rank, (mentor_index, score, factors))| {
                 let mentor = &mentors[mentor_index];
+                let matched_reasons = self.generate_match_reasons(learner, mentor, &factors, score);
                 MatchResult {
                     mentor_id: mentor.id,
                     score,
-                    reasoning: self.generate_reasoning(&factors, rank + 1),
+                    reasoning: self.generate_reasoning(&matched_reasons, rank + 1),
+                    matched_reasons,
                     compatibility_factors: factors,
                 }
             })
@@ -55,8 +84,8 @@ impl MentorMatcher {
         top_matches
     }
 
-    fn calculate_compatibility_score(&self, learner: &User, mentor: &Mentor) -> f64 {
-        let factors = self.calculate_compatibility_factors(learner, mentor);
+    fn calculate_compatibility_score(&self, learner: &User, mentor: &Mentor, skill_match_threshold: f64, normalize_skills: bool) -> f64 {
+        let factors = self.calculate_compatibility_factors(learner, mentor, skill_match_threshold, normalize_skills);
 
         // Weighted scoring algorithm
         let weights = CompatibilityWeights {
@@ -68,82 +97,92 @@ impl MentorMatcher {
         };
 
         let score = (factors.skill_overlap * weights.skill_overlap) +
-                   ((factors.location_match as i32 as f64) * weights.location_match) +
+                   (factors.location_affinity * weights.location_match) +
                    (factors.availability_match * weights.availability_match) +
                    (factors.experience_compatibility * weights.experience_compatibility) +
                    (factors.teaching_style_match * weights.teaching_style_match);
 
         // Normalize to 0-100 scale
-        (score * 100.0).min(100.0).max(0.0)
+        (score * 100.0).clamp(0.0, 100.0)
+    }
+
+    /// Resolves a single ranking rule to the comparable value it ranks on,
+    /// higher-is-better in all cases. `Weighted` defers to the existing
+    /// scalar score so `[Weighted]` reproduces the original ordering.
+    fn ranking_rule_value(&self, rule: RankingRule, mentor: &Mentor, score: f64, factors: &CompatibilityFactors) -> f64 {
+        match rule {
+            RankingRule::Skill => factors.skill_overlap,
+            RankingRule::Experience => factors.experience_compatibility,
+            RankingRule::Rating => mentor.rating,
+            RankingRule::Availability => factors.availability_match,
+            RankingRule::Location => factors.location_affinity,
+            RankingRule::Weighted => score,
+        }
     }
 
-    fn calculate_compatibility_factors(&self, learner: &User, mentor: &Mentor) -> CompatibilityFactors {
+    fn calculate_compatibility_factors(&self, learner: &User, mentor: &Mentor, skill_match_threshold: f64, normalize_skills: bool) -> CompatibilityFactors {
+        let (learner_skills, mentor_expertise) = if normalize_skills {
+            (normalization::normalize_skills(&learner.skills), normalization::normalize_skills(&mentor.expertise))
+        } else {
+            (learner.skills.clone(), mentor.expertise.clone())
+        };
+
         CompatibilityFactors {
-            skill_overlap: self.calculate_skill_overlap(&learner.skills, &mentor.expertise),
-            location_match: self.calculate_location_match(&learner.location, &mentor.location),
-            availability_match: self.calculate_availability_match(learner.availability, mentor.availability),
+            skill_overlap: self.calculate_skill_overlap(&learner_skills, &mentor_expertise, skill_match_threshold),
+            location_affinity: geography::location_affinity(&learner.location, &mentor.location),
+            availability_match: self.calculate_availability_match(learner.availability, mentor.availability, &learner.location, &mentor.location),
             experience_compatibility: self.calculate_experience_compatibility(learner.experience_level.as_str(), mentor.experience_years),
             teaching_style_match: self.calculate_teaching_style_match(learner, mentor),
+            normalized_learner_skills: learner_skills,
+            normalized_mentor_expertise: mentor_expertise,
         }
     }
 
-    fn calculate_skill_overlap(&self, learner_skills: &[String], mentor_expertise: &[String]) -> f64 {
+    /// Bipartite greedy assignment: each learner skill is matched against its
+    /// single best fuzzy similarity among the mentor's expertise, and the
+    /// resulting similarities are averaged. Skills are only counted as a
+    /// match once their similarity clears `threshold`.
+    fn calculate_skill_overlap(&self, learner_skills: &[String], mentor_expertise: &[String], threshold: f64) -> f64 {
         if learner_skills.is_empty() || mentor_expertise.is_empty() {
             return 0.0;
         }
 
-        let learner_set: std::collections::HashSet<_> = learner_skills.iter().collect();
-        let mentor_set: std::collections::HashSet<_> = mentor_expertise.iter().collect();
-
-        let intersection: std::collections::HashSet<_> = learner_set.intersection(&mentor_set).collect();
-        let union = learner_set.len() + mentor_set.len() - intersection.len();
-
-        if union == 0 {
-            0.0
-        } else {
-            intersection.len() as f64 / union as f64
-        }
-    }
-
-    fn calculate_location_match(&self, learner_location: &str, mentor_location: &str) -> bool {
-        // Simple string matching - in production, use geocoding and distance calculation
-        learner_location.to_lowercase() == mentor_location.to_lowercase() ||
-        self.is_same_region(learner_location, mentor_location)
-    }
-
-    fn is_same_region(&self, loc1: &str, loc2: &str) -> bool {
-        // Simplified region matching - expand based on your geographic needs
-        let regions = [
-            ("africa", ["nigeria", "kenya", "south africa", "ghana", "uganda"]),
-            ("europe", ["uk", "germany", "france", "spain", "italy"]),
-            ("asia", ["india", "china", "japan", "singapore"]),
-            ("americas", ["usa", "canada", "brazil", "mexico"]),
-        ];
-
-        for (_, countries) in regions.iter() {
-            let loc1_in_region = countries.iter().any(|&c| loc1.to_lowercase().contains(c));
-            let loc2_in_region = countries.iter().any(|&c| loc2.to_lowercase().contains(c));
-            if loc1_in_region && loc2_in_region {
-                return true;
-            }
-        }
+        let total: f64 = learner_skills
+            .iter()
+            .map(|skill| {
+                mentor_expertise
+                    .iter()
+                    .map(|expertise| fuzzy_skill_similarity(skill, expertise))
+                    .filter(|&similarity| similarity >= threshold)
+                    .fold(0.0_f64, f64::max)
+            })
+            .sum();
 
-        false
+        total / learner_skills.len() as f64
     }
 
-    fn calculate_availability_match(&self, learner_hours: i32, mentor_hours: i32) -> f64 {
+    /// Combines raw weekly-hours compatibility with timezone overlap, since
+    /// a mentor with plenty of free hours in the wrong timezone is still a
+    /// poor availability match for real-time mentoring.
+    fn calculate_availability_match(&self, learner_hours: i32, mentor_hours: i32, learner_location: &str, mentor_location: &str) -> f64 {
+        // A mentor advertising zero hours is unavailable full stop - timezone
+        // overlap can't make up for that, so short-circuit before blending.
         if mentor_hours == 0 {
             return 0.0;
         }
 
         let ratio = learner_hours as f64 / mentor_hours as f64;
-        // Optimal match when learner needs <= mentor availability
-        if ratio <= 1.0 {
+        let hours_match = if ratio <= 1.0 {
+            // Optimal match when learner needs <= mentor availability
             1.0
         } else {
             // Penalty for mentor being over-committed
             (1.0 / ratio).max(0.1)
-        }
+        };
+
+        let timezone_overlap = geography::timezone_overlap_fraction(learner_location, mentor_location);
+
+        (hours_match * 0.7) + (timezone_overlap * 0.3)
     }
 
     fn calculate_experience_compatibility(&self, learner_level: &str, mentor_years: i32) -> f64 {
@@ -188,33 +227,139 @@ impl MentorMatcher {
         }
     }
 
-    fn generate_reasoning(&self, factors: &CompatibilityFactors, rank: usize) -> String {
+    /// Builds the structured reasons behind a match, in deterministic
+    /// priority order, so two candidates with equal scores always explain
+    /// themselves identically regardless of evaluation order.
+    fn generate_match_reasons(&self, _learner: &User, _mentor: &Mentor, factors: &CompatibilityFactors, score: f64) -> Vec<MatchReason> {
         let mut reasons = Vec::new();
 
         if factors.skill_overlap > 0.7 {
-            reasons.push("Excellent skill alignment".to_string());
+            reasons.push(MatchReason::ExcellentSkillAlignment { skill_overlap: factors.skill_overlap });
         } else if factors.skill_overlap > 0.4 {
-            reasons.push("Good skill overlap".to_string());
+            reasons.push(MatchReason::GoodSkillOverlap { skill_overlap: factors.skill_overlap });
         }
 
-        if factors.location_match {
-            reasons.push("Location match".to_string());
+        if factors.location_affinity >= 1.0 {
+            reasons.push(MatchReason::SameLocation);
+        } else if factors.location_affinity >= 0.4 {
+            reasons.push(MatchReason::SameRegion);
         }
 
         if factors.availability_match > 0.8 {
-            reasons.push("Availability compatibility".to_string());
+            reasons.push(MatchReason::AvailabilityFits { availability_match: factors.availability_match });
         }
 
         if factors.experience_compatibility > 0.8 {
-            reasons.push("Experience level match".to_string());
+            reasons.push(MatchReason::ExperienceLevelMatch { experience_compatibility: factors.experience_compatibility });
+        }
+
+        if factors.teaching_style_match > 0.8 {
+            reasons.push(MatchReason::TeachingStyleFit { teaching_style_match: factors.teaching_style_match });
         }
 
         if reasons.is_empty() {
-            format!("Rank {} match based on overall compatibility", rank)
-        } else {
-            format!("Rank {} match: {}", rank, reasons.join(", "))
+            reasons.push(MatchReason::OverallCompatibility { score });
+        }
+
+        reasons.sort_by_key(MatchReason::priority);
+        reasons
+    }
+
+    fn generate_reasoning(&self, reasons: &[MatchReason], rank: usize) -> String {
+        let descriptions: Vec<String> = reasons.iter().map(MatchReason::describe).collect();
+        format!("Rank {} match: {}", rank, descriptions.join(", "))
+    }
+}
+
+/// Builds a `u64` bitmask where bit N is set if `s` (lowercased) contains
+/// character N of `a-z0-9`. Used as a cheap prefilter before the more
+/// expensive fuzzy scoring pass: a candidate missing a character the query
+/// needs can never be a good match.
+fn char_bag(s: &str) -> u64 {
+    let mut bag = 0u64;
+    for c in s.to_lowercase().chars() {
+        let bit = match c {
+            'a'..='z' => Some(c as u32 - 'a' as u32),
+            '0'..='9' => Some(26 + (c as u32 - '0' as u32)),
+            _ => None,
+        };
+        if let Some(bit) = bit {
+            bag |= 1u64 << bit;
+        }
+    }
+    bag
+}
+
+/// Fuzzy similarity between two skill labels, normalized to 0.0-1.0. The
+/// char-bag prefilter is directional (it demands the candidate contain every
+/// query character), so skill labels can disagree on which one is the
+/// "bigger" string depending on phrasing ("react" vs "reactjs", "python" vs
+/// "Python 3") - we score both orientations and keep the better one.
+fn fuzzy_skill_similarity(a: &str, b: &str) -> f64 {
+    fuzzy_skill_similarity_oriented(a, b).max(fuzzy_skill_similarity_oriented(b, a))
+}
+
+/// Scores `query` against `candidate` in one direction: rejects pairs that
+/// can't possibly match via a char-bag prefilter, then greedily walks the
+/// query left-to-right looking for each character in order in the
+/// candidate, rewarding consecutive runs and matches that land on a word
+/// boundary (start of string, or just after a space/hyphen).
+fn fuzzy_skill_similarity_oriented(query: &str, candidate: &str) -> f64 {
+    let query = query.to_lowercase();
+    let candidate = candidate.to_lowercase();
+
+    if query.is_empty() {
+        return 0.0;
+    }
+
+    let query_bag = char_bag(&query);
+    let candidate_bag = char_bag(&candidate);
+    if (query_bag & candidate_bag) != query_bag {
+        return 0.0;
+    }
+
+    let query_chars: Vec<char> = query.chars().collect();
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+
+    let mut score = 0.0;
+    let mut search_from = 0;
+
+    for &qc in &query_chars {
+        let found = candidate_chars[search_from..]
+            .iter()
+            .position(|&cc| cc == qc)
+            .map(|offset| search_from + offset);
+
+        let Some(match_index) = found else {
+            continue;
+        };
+
+        let is_word_boundary = match_index == 0
+            || matches!(candidate_chars[match_index - 1], ' ' | '-');
+        let is_consecutive = match_index == search_from;
+
+        let mut weight = 1.0;
+        if is_consecutive {
+            weight += 0.5;
         }
+        if is_word_boundary {
+            weight += 0.5;
+        }
+
+        score += weight;
+        search_from = match_index + 1;
     }
+
+    // Normalizing purely by query length lets a short query that's a literal
+    // prefix of a much longer, unrelated candidate saturate to ~1.0 (e.g.
+    // "java" fully and consecutively matches inside "javascript"). Averaging
+    // the query and candidate lengths before scaling means leftover,
+    // unaccounted candidate characters pull the score down instead of being
+    // ignored, while same-length pairs are unaffected and near-length pairs
+    // (suffixes separated by a word boundary like "python" vs "python 3",
+    // which scores ~0.9) lose only a little.
+    let max_possible_weight = (query_chars.len() + candidate_chars.len()) as f64 / 2.0 * 1.5;
+    (score / max_possible_weight).min(1.0)
 }
 
 struct CompatibilityWeights {
@@ -236,16 +381,142 @@ mod tests {
         let learner_skills = vec!["python".to_string(), "django".to_string()];
         let mentor_expertise = vec!["python".to_string(), "rust".to_string()];
 
-        let overlap = matcher.calculate_skill_overlap(&learner_skills, &mentor_expertise);
-        assert_eq!(overlap, 1.0 / 3.0); // 1 intersection, 3 union
+        // "python" matches exactly (1.0); "django" has no close match in
+        // ["python", "rust"], so it contributes 0.0 and the average is 0.5.
+        let overlap = matcher.calculate_skill_overlap(&learner_skills, &mentor_expertise, DEFAULT_SKILL_MATCH_THRESHOLD);
+        assert_eq!(overlap, 0.5);
+    }
+
+    #[test]
+    fn test_fuzzy_skill_similarity_near_misses() {
+        assert_eq!(fuzzy_skill_similarity("python", "python"), 1.0);
+        assert!(fuzzy_skill_similarity("python", "python 3") >= DEFAULT_SKILL_MATCH_THRESHOLD);
+        assert!(fuzzy_skill_similarity("reactjs", "react") >= DEFAULT_SKILL_MATCH_THRESHOLD);
+        assert_eq!(fuzzy_skill_similarity("python", "rust"), 0.0);
+    }
+
+    #[test]
+    fn test_fuzzy_skill_similarity_rejects_unrelated_prefixes() {
+        // A short skill that happens to be a literal prefix of a much longer,
+        // unrelated one must not be treated as a near-perfect match.
+        assert!(fuzzy_skill_similarity("java", "javascript") < DEFAULT_SKILL_MATCH_THRESHOLD);
+        assert!(fuzzy_skill_similarity("c", "css") < DEFAULT_SKILL_MATCH_THRESHOLD);
+    }
+
+    #[test]
+    fn test_match_reasons_are_sorted_by_priority() {
+        let matcher = MentorMatcher::new();
+
+        let factors = CompatibilityFactors {
+            skill_overlap: 0.9,
+            location_affinity: 0.7,
+            availability_match: 0.9,
+            experience_compatibility: 0.9,
+            teaching_style_match: 0.9,
+            normalized_learner_skills: vec!["python".to_string()],
+            normalized_mentor_expertise: vec!["python".to_string()],
+        };
+
+        let learner = sample_user("Nigeria");
+        let mentor = sample_mentor("Kenya"); // same region, not same location
+
+        let reasons = matcher.generate_match_reasons(&learner, &mentor, &factors, 90.0);
+
+        let priorities: Vec<u8> = reasons.iter().map(MatchReason::priority).collect();
+        let mut sorted_priorities = priorities.clone();
+        sorted_priorities.sort();
+        assert_eq!(priorities, sorted_priorities);
+        assert_eq!(reasons[0], MatchReason::ExcellentSkillAlignment { skill_overlap: 0.9 });
+        assert!(reasons.contains(&MatchReason::SameRegion));
+    }
+
+    fn sample_user(location: &str) -> User {
+        User {
+            id: uuid::Uuid::new_v4(),
+            skills: vec!["python".to_string()],
+            learning_goals: vec![],
+            location: location.to_string(),
+            availability: 5,
+            experience_level: "beginner".to_string(),
+            preferred_languages: vec![],
+        }
+    }
+
+    fn sample_mentor(location: &str) -> Mentor {
+        Mentor {
+            id: uuid::Uuid::new_v4(),
+            expertise: vec!["python".to_string()],
+            location: location.to_string(),
+            availability: 10,
+            experience_years: 5,
+            rating: 4.8,
+            hourly_rate: 40,
+            teaching_style: "structured".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ranking_rules_break_ties_lexicographically() {
+        let matcher = MentorMatcher::new();
+        let learner = sample_user("Nigeria");
+
+        // Both mentors are identical except for rating, so a plain Weighted
+        // sort is free to order them either way (scores tie); ranking on
+        // Rating first must always put the higher-rated mentor ahead.
+        let mut lower_rated = sample_mentor("Nigeria");
+        lower_rated.rating = 4.0;
+        let mut higher_rated = sample_mentor("Nigeria");
+        higher_rated.rating = 4.9;
+
+        let mentors = vec![lower_rated.clone(), higher_rated.clone()];
+        let matches = matcher.find_matches(&learner, &mentors, 2, DEFAULT_SKILL_MATCH_THRESHOLD, &[RankingRule::Rating, RankingRule::Weighted], true);
+
+        assert_eq!(matches[0].mentor_id, higher_rated.id);
+        assert_eq!(matches[1].mentor_id, lower_rated.id);
+    }
+
+    #[test]
+    fn test_matching_is_deterministic_and_order_independent() {
+        let matcher = MentorMatcher::new();
+        let learner = sample_user("Nigeria");
+
+        let mentor_a = sample_mentor("Nigeria");
+        let mut mentor_b = sample_mentor("Kenya");
+        mentor_b.rating = 3.0;
+        mentor_b.experience_years = 1;
+
+        let forward = matcher.find_matches(&learner, &[mentor_a.clone(), mentor_b.clone()], 2, DEFAULT_SKILL_MATCH_THRESHOLD, DEFAULT_RANKING_RULES, true);
+        let reversed = matcher.find_matches(&learner, &[mentor_b, mentor_a], 2, DEFAULT_SKILL_MATCH_THRESHOLD, DEFAULT_RANKING_RULES, true);
+
+        let forward_ids: Vec<_> = forward.iter().map(|m| m.mentor_id).collect();
+        let reversed_ids: Vec<_> = reversed.iter().map(|m| m.mentor_id).collect();
+        assert_eq!(forward_ids, reversed_ids);
+
+        let forward_scores: Vec<_> = forward.iter().map(|m| m.score).collect();
+        let reversed_scores: Vec<_> = reversed.iter().map(|m| m.score).collect();
+        assert_eq!(forward_scores, reversed_scores);
+
+        let forward_reasons: Vec<_> = forward.iter().map(|m| m.matched_reasons.clone()).collect();
+        let reversed_reasons: Vec<_> = reversed.iter().map(|m| m.matched_reasons.clone()).collect();
+        assert_eq!(forward_reasons, reversed_reasons);
     }
 
     #[test]
-    fn test_location_match() {
+    fn test_matching_is_order_independent_when_mentors_tie_on_every_rule() {
         let matcher = MentorMatcher::new();
+        let learner = sample_user("Nigeria");
+
+        // Identical on every factor the default ranking rules can see, so
+        // without a final id tiebreak a stable sort would just preserve
+        // whatever order the mentors were passed in.
+        let mentor_a = sample_mentor("Nigeria");
+        let mentor_b = sample_mentor("Nigeria");
+
+        let forward = matcher.find_matches(&learner, &[mentor_a.clone(), mentor_b.clone()], 2, DEFAULT_SKILL_MATCH_THRESHOLD, DEFAULT_RANKING_RULES, true);
+        let reversed = matcher.find_matches(&learner, &[mentor_b, mentor_a], 2, DEFAULT_SKILL_MATCH_THRESHOLD, DEFAULT_RANKING_RULES, true);
 
-        assert!(matcher.calculate_location_match("Nigeria", "Nigeria"));
-        assert!(matcher.is_same_region("Kenya", "South Africa"));
-        assert!(!matcher.is_same_region("Nigeria", "Germany"));
+        let forward_ids: Vec<_> = forward.iter().map(|m| m.mentor_id).collect();
+        let reversed_ids: Vec<_> = reversed.iter().map(|m| m.mentor_id).collect();
+        assert_eq!(forward_ids, reversed_ids);
     }
 }
\ No newline at end of file
@@ -30,17 +30,78 @@ pub struct Mentor {
 pub struct MatchResult {
     pub mentor_id: Uuid,
     pub score: f64,
+    /// Human-readable summary derived from `matched_reasons`, kept for
+    /// backwards-compatible display; prefer `matched_reasons` for
+    /// programmatic filtering, grouping, or localization.
     pub reasoning: String,
+    pub matched_reasons: Vec<MatchReason>,
     pub compatibility_factors: CompatibilityFactors,
 }
 
+/// A single factor that contributed to a match, carrying the value that
+/// triggered it. Variants are declared in fixed priority order (highest
+/// first) so reasons can be sorted deterministically via `priority`,
+/// ensuring two equal-scoring matches always explain themselves identically.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MatchReason {
+    ExcellentSkillAlignment { skill_overlap: f64 },
+    GoodSkillOverlap { skill_overlap: f64 },
+    SameLocation,
+    SameRegion,
+    AvailabilityFits { availability_match: f64 },
+    ExperienceLevelMatch { experience_compatibility: f64 },
+    TeachingStyleFit { teaching_style_match: f64 },
+    OverallCompatibility { score: f64 },
+}
+
+impl MatchReason {
+    /// Fixed rank used to order reasons deterministically; lower sorts first.
+    pub fn priority(&self) -> u8 {
+        match self {
+            MatchReason::ExcellentSkillAlignment { .. } => 0,
+            MatchReason::GoodSkillOverlap { .. } => 1,
+            MatchReason::SameLocation => 2,
+            MatchReason::SameRegion => 3,
+            MatchReason::AvailabilityFits { .. } => 4,
+            MatchReason::ExperienceLevelMatch { .. } => 5,
+            MatchReason::TeachingStyleFit { .. } => 6,
+            MatchReason::OverallCompatibility { .. } => 7,
+        }
+    }
+
+    /// Human-readable description of this reason, used to derive the
+    /// convenience `reasoning` string on `MatchResult`.
+    pub fn describe(&self) -> String {
+        match self {
+            MatchReason::ExcellentSkillAlignment { .. } => "Excellent skill alignment".to_string(),
+            MatchReason::GoodSkillOverlap { .. } => "Good skill overlap".to_string(),
+            MatchReason::SameLocation => "Location match".to_string(),
+            MatchReason::SameRegion => "Same region".to_string(),
+            MatchReason::AvailabilityFits { .. } => "Availability compatibility".to_string(),
+            MatchReason::ExperienceLevelMatch { .. } => "Experience level match".to_string(),
+            MatchReason::TeachingStyleFit { .. } => "Teaching style fit".to_string(),
+            MatchReason::OverallCompatibility { .. } => "overall compatibility".to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompatibilityFactors {
     pub skill_overlap: f64,
-    pub location_match: bool,
+    /// Graded 0.0-1.0 geographic affinity: 1.0 same country, ~0.7 same
+    /// subregion, ~0.4 same continent, 0.0 otherwise.
+    pub location_affinity: f64,
     pub availability_match: f64,
     pub experience_compatibility: f64,
     pub teaching_style_match: f64,
+    /// Learner skills as actually scored, after typo normalization (or
+    /// unchanged, if normalization was disabled). Lets callers audit why a
+    /// skill did or didn't contribute to `skill_overlap`.
+    pub normalized_learner_skills: Vec<String>,
+    /// Mentor expertise as actually scored, after typo normalization (or
+    /// unchanged, if normalization was disabled).
+    pub normalized_mentor_expertise: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,9 +109,33 @@ pub struct MatchingRequest {
     pub learner: User,
     pub mentors: Vec<Mentor>,
     pub limit: Option<usize>,
+    /// Minimum fuzzy similarity (0.0-1.0) for a learner skill to count as a
+    /// match against a mentor's expertise. Defaults to 0.8 when omitted.
+    pub skill_match_threshold: Option<f64>,
+    /// Ordered tie-break rules applied lexicographically when sorting
+    /// matches: candidates are compared on the first rule, and only fall
+    /// through to the next rule when tied (within an epsilon) on the
+    /// current one. Defaults to `[Weighted]`, i.e. today's single-score sort.
+    pub ranking_rules: Option<Vec<RankingRule>>,
+    /// Skip typo-tolerant skill normalization for callers who already
+    /// pre-normalize their skill lists. Defaults to `false` (normalize).
+    pub disable_skill_normalization: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// One criterion in a `ranking_rules` chain. `Weighted` falls back to the
+/// existing `CompatibilityWeights` scalar score; `Rating` ranks on the
+/// mentor's rating; the rest rank directly on a single compatibility factor.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RankingRule {
+    Skill,
+    Experience,
+    Rating,
+    Availability,
+    Location,
+    Weighted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchingResponse {
     pub matches: Vec<MatchResult>,
     pub processing_time_ms: u64,
@@ -0,0 +1,120 @@
+//! Typo-tolerant skill normalization: snaps free-text skill tokens ("kubernets",
+//! "postgre", "javscript") onto a canonical vocabulary before they're fed into
+//! skill-overlap scoring, the way Meilisearch scales typo tolerance by word
+//! length.
+
+/// Canonical skill vocabulary. In production this would likely come from a
+/// database or config; for now it's a representative seed list.
+const CANONICAL_SKILLS: &[&str] = &[
+    "python", "javascript", "typescript", "rust", "golang", "java",
+    "kubernetes", "docker", "postgres", "postgresql", "mysql", "mongodb", "redis",
+    "react", "reactjs", "vue", "angular", "django", "flask", "nodejs",
+    "aws", "azure", "gcp", "graphql", "rest", "css", "html",
+];
+
+/// Maximum edits allowed for a token of `len` characters: 0 for tokens under
+/// 4 chars, 1 for 4-7 chars, 2 for 8+ chars.
+fn max_edits_for(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Bounded Levenshtein distance: only fills the diagonal band of width
+/// `2 * max_edits + 1` around the main diagonal, and bails out as soon as a
+/// row's minimum exceeds `max_edits`, since no cell in a later row can then
+/// recover. Returns `None` when the true distance exceeds `max_edits`.
+fn bounded_edit_distance(a: &[char], b: &[char], max_edits: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max_edits {
+        return None;
+    }
+
+    const UNREACHABLE: usize = usize::MAX / 2;
+    let mut prev = vec![UNREACHABLE; b.len() + 1];
+    let mut curr = vec![UNREACHABLE; b.len() + 1];
+
+    for (j, slot) in prev.iter_mut().enumerate().take(max_edits.min(b.len()) + 1) {
+        *slot = j;
+    }
+
+    for i in 1..=a.len() {
+        let lo = i.saturating_sub(max_edits);
+        let hi = (i + max_edits).min(b.len());
+        curr.iter_mut().for_each(|v| *v = UNREACHABLE);
+        if lo == 0 {
+            curr[0] = i;
+        }
+
+        let mut row_min = UNREACHABLE;
+        for j in lo.max(1)..=hi {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let deletion = prev[j] + 1;
+            let insertion = curr[j - 1] + 1;
+            let substitution = prev[j - 1] + cost;
+            curr[j] = deletion.min(insertion).min(substitution);
+            row_min = row_min.min(curr[j]);
+        }
+
+        if row_min > max_edits {
+            return None;
+        }
+
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    let distance = prev[b.len()];
+    (distance <= max_edits).then_some(distance)
+}
+
+/// Snaps `token` to its closest canonical skill within the length-scaled
+/// edit-distance budget. Falls back to the lowercased token unchanged when
+/// nothing in the vocabulary is close enough.
+pub fn normalize_skill(token: &str) -> String {
+    let token = token.to_lowercase();
+    let token_chars: Vec<char> = token.chars().collect();
+    let max_edits = max_edits_for(token_chars.len());
+
+    CANONICAL_SKILLS
+        .iter()
+        .filter_map(|&canonical| {
+            let canonical_chars: Vec<char> = canonical.chars().collect();
+            bounded_edit_distance(&token_chars, &canonical_chars, max_edits).map(|d| (canonical, d))
+        })
+        .min_by_key(|&(_, distance)| distance)
+        .map(|(canonical, _)| canonical.to_string())
+        .unwrap_or(token)
+}
+
+/// Normalizes a full skill list, preserving order and length.
+pub fn normalize_skills(tokens: &[String]) -> Vec<String> {
+    tokens.iter().map(|token| normalize_skill(token)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalizes_common_typos() {
+        assert_eq!(normalize_skill("kubernets"), "kubernetes");
+        // "postgre" is only 1 edit from "postgres" but 3 from "postgresql",
+        // and a 7-char token gets a 1-edit budget, so it snaps to "postgres".
+        assert_eq!(normalize_skill("postgre"), "postgres");
+        assert_eq!(normalize_skill("javscript"), "javascript");
+    }
+
+    #[test]
+    fn test_leaves_unrelated_tokens_unchanged() {
+        assert_eq!(normalize_skill("blockchain"), "blockchain");
+    }
+
+    #[test]
+    fn test_short_tokens_require_exact_match() {
+        // "css" is in the vocabulary and under 4 chars, so it must match
+        // exactly; "csx" is one edit away but gets zero edits of budget.
+        assert_eq!(normalize_skill("css"), "css");
+        assert_eq!(normalize_skill("csx"), "csx");
+    }
+}
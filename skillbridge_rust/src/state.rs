@@ -0,0 +1,252 @@
+//! Shared application state: thread-safe request counters and a small
+//! LRU/TTL response cache, injected into handlers via `web::Data`. Replaces
+//! the old `static mut` counters, which raced under Actix's multi-threaded
+//! workers.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::models::{Mentor, MatchingRequest, MatchingResponse, MatchingStats, RankingRule};
+
+const CACHE_CAPACITY: usize = 256;
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Stable cache key for a matching request: hashes the learner, a content
+/// digest of the mentors (sorted by id so input ordering doesn't matter), the
+/// result limit, and the ranking configuration that would otherwise reorder
+/// identical inputs differently. Hashing full mentor records, not just their
+/// ids, means a profile edit (rating, expertise, location, ...) invalidates
+/// the cache instead of silently serving the stale match.
+pub fn compute_cache_key(
+    req: &MatchingRequest,
+    ranking_rules: &[RankingRule],
+    skill_match_threshold: f64,
+    normalize_skills: bool,
+    limit: usize,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    if let Ok(learner_json) = serde_json::to_string(&req.learner) {
+        learner_json.hash(&mut hasher);
+    }
+
+    let mut mentors: Vec<&Mentor> = req.mentors.iter().collect();
+    mentors.sort_by_key(|mentor| mentor.id);
+    if let Ok(mentors_json) = serde_json::to_string(&mentors) {
+        mentors_json.hash(&mut hasher);
+    }
+
+    limit.hash(&mut hasher);
+    if let Ok(rules_json) = serde_json::to_string(ranking_rules) {
+        rules_json.hash(&mut hasher);
+    }
+    skill_match_threshold.to_bits().hash(&mut hasher);
+    normalize_skills.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+struct MatchCache {
+    entries: HashMap<u64, (MatchingResponse, Instant)>,
+    insertion_order: VecDeque<u64>,
+}
+
+impl MatchCache {
+    fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            insertion_order: VecDeque::new(),
+        }
+    }
+
+    fn get(&mut self, key: u64) -> Option<MatchingResponse> {
+        match self.entries.get(&key) {
+            Some((response, inserted_at)) if inserted_at.elapsed() <= CACHE_TTL => {
+                let response = response.clone();
+                self.touch(key);
+                Some(response)
+            }
+            Some(_) => {
+                self.entries.remove(&key);
+                self.insertion_order.retain(|&k| k != key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&mut self, key: u64, response: MatchingResponse) {
+        if self.entries.contains_key(&key) {
+            self.touch(key);
+        } else {
+            self.insertion_order.push_back(key);
+            if self.insertion_order.len() > CACHE_CAPACITY {
+                if let Some(oldest) = self.insertion_order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        self.entries.insert(key, (response, Instant::now()));
+    }
+
+    /// Moves `key` to the back of the eviction order, marking it
+    /// most-recently-used so a hot key survives while colder keys are
+    /// evicted first.
+    fn touch(&mut self, key: u64) {
+        self.insertion_order.retain(|&k| k != key);
+        self.insertion_order.push_back(key);
+    }
+}
+
+pub struct AppState {
+    total_requests: AtomicU64,
+    total_processing_time_ms: AtomicU64,
+    cache_hits: AtomicU64,
+    cache: Mutex<MatchCache>,
+    start_time: Instant,
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        Self {
+            total_requests: AtomicU64::new(0),
+            total_processing_time_ms: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            cache: Mutex::new(MatchCache::new()),
+            start_time: Instant::now(),
+        }
+    }
+
+    pub fn record_request(&self, processing_time_ms: u64) {
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+        self.total_processing_time_ms.fetch_add(processing_time_ms, Ordering::Relaxed);
+    }
+
+    pub fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get_cached(&self, key: u64) -> Option<MatchingResponse> {
+        self.cache.lock().unwrap().get(key)
+    }
+
+    pub fn cache_response(&self, key: u64, response: MatchingResponse) {
+        self.cache.lock().unwrap().insert(key, response);
+    }
+
+    pub fn stats(&self) -> MatchingStats {
+        let total_requests = self.total_requests.load(Ordering::Relaxed);
+        let total_processing_time = self.total_processing_time_ms.load(Ordering::Relaxed);
+        let cache_hits = self.cache_hits.load(Ordering::Relaxed);
+
+        let average_processing_time_ms = if total_requests > 0 {
+            total_processing_time as f64 / total_requests as f64
+        } else {
+            0.0
+        };
+
+        let cache_hit_rate = if total_requests > 0 {
+            cache_hits as f64 / total_requests as f64
+        } else {
+            0.0
+        };
+
+        MatchingStats {
+            total_requests,
+            average_processing_time_ms,
+            cache_hit_rate,
+            uptime_seconds: self.start_time.elapsed().as_secs(),
+        }
+    }
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_response(tag: u64) -> MatchingResponse {
+        MatchingResponse {
+            matches: vec![],
+            processing_time_ms: tag,
+            algorithm_version: "1.0.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used_not_least_recently_inserted() {
+        let mut cache = MatchCache::new();
+        for key in 0..CACHE_CAPACITY as u64 {
+            cache.insert(key, sample_response(key));
+        }
+
+        // Key 0 is the oldest insertion, but touching it via `get` should
+        // mark it most-recently-used and spare it from eviction.
+        assert!(cache.get(0).is_some());
+
+        cache.insert(CACHE_CAPACITY as u64, sample_response(CACHE_CAPACITY as u64));
+
+        assert!(cache.get(0).is_some(), "recently-used key 0 should survive eviction");
+        assert!(cache.get(1).is_none(), "untouched key 1 should be evicted as least-recently-used");
+    }
+
+    #[test]
+    fn test_cache_key_changes_when_mentor_content_changes() {
+        use crate::models::{Mentor, User};
+
+        let learner = User {
+            id: uuid::Uuid::new_v4(),
+            skills: vec!["python".to_string()],
+            learning_goals: vec![],
+            location: "Nigeria".to_string(),
+            availability: 10,
+            experience_level: "beginner".to_string(),
+            preferred_languages: vec![],
+        };
+        let mentor_id = uuid::Uuid::new_v4();
+        let mentor = Mentor {
+            id: mentor_id,
+            expertise: vec!["python".to_string()],
+            location: "Nigeria".to_string(),
+            availability: 15,
+            experience_years: 3,
+            rating: 4.5,
+            hourly_rate: 50,
+            teaching_style: "structured".to_string(),
+        };
+
+        let mut updated_mentor = mentor.clone();
+        updated_mentor.rating = 5.0;
+
+        let req_before = MatchingRequest {
+            learner: learner.clone(),
+            mentors: vec![mentor],
+            limit: Some(5),
+            skill_match_threshold: None,
+            ranking_rules: None,
+            disable_skill_normalization: None,
+        };
+        let req_after = MatchingRequest {
+            learner,
+            mentors: vec![updated_mentor],
+            limit: Some(5),
+            skill_match_threshold: None,
+            ranking_rules: None,
+            disable_skill_normalization: None,
+        };
+
+        let key_before = compute_cache_key(&req_before, &[RankingRule::Weighted], 0.8, true, 5);
+        let key_after = compute_cache_key(&req_after, &[RankingRule::Weighted], 0.8, true, 5);
+
+        assert_ne!(key_before, key_after, "a mentor profile edit (same id) must invalidate the cache key");
+    }
+}